@@ -0,0 +1,9 @@
+//! Seed constants used to derive the program's PDAs.
+//! Kept in one place (and re-exported through wasm.rs) so on-chain instructions
+//! and client code can never drift apart on the bytes used to seed an address.
+
+pub const AUTHORITY_SEED: &str = "authority";
+pub const CUSTODY_SEED: &str = "custody";
+pub const STAKE_ACCOUNT_METADATA_SEED: &str = "stake_metadata";
+pub const CONFIG_SEED: &str = "config";
+pub const VOTER_RECORD_SEED: &str = "voter_weight";
@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Position not in use")]
+    PositionNotInUse,
+    #[msg("Generic overflow")]
+    GenericOverflow,
+    #[msg("No free position slots available")]
+    TooManyPositions,
+    #[msg("Vesting schedule control points must be strictly increasing in time and non-decreasing in vested fraction")]
+    VestingScheduleNotMonotonic,
+}
@@ -0,0 +1,12 @@
+use anchor_lang::prelude::*;
+
+pub mod context;
+pub mod error;
+pub mod state;
+pub mod utils;
+pub mod wasm;
+
+pub use state::positions::{Position, PositionData, PositionState, MAX_POSITIONS};
+pub use state::vesting::VestingSchedule;
+
+declare_id!("Govrn1111111111111111111111111111111111111");
@@ -0,0 +1,2 @@
+pub mod positions;
+pub mod vesting;
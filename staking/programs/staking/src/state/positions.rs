@@ -0,0 +1,306 @@
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// Maximum number of positions a single stake account can hold.
+pub const MAX_POSITIONS: usize = 20;
+
+#[wasm_bindgen]
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum PositionState {
+    UNLOCKED,
+    LOCKING,
+    LOCKED,
+    UNLOCKING,
+}
+
+/// A single stake position, i.e. some amount of tokens locked starting at `activation_epoch`
+/// and, once unlocking has been requested, counting down `unlocking_duration` epochs from
+/// `unlocking_start` before it becomes withdrawable again.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct Position {
+    pub amount: u64,
+    pub activation_epoch: u64,
+    pub unlocking_start: Option<u64>,
+}
+
+impl Position {
+    pub fn get_current_position(
+        &self,
+        current_epoch: u64,
+        unlocking_duration: u8,
+    ) -> Result<PositionState> {
+        if self.activation_epoch > current_epoch {
+            return Ok(PositionState::LOCKING);
+        }
+
+        match self.unlocking_start {
+            None => Ok(PositionState::LOCKED),
+            Some(unlocking_start) => {
+                if current_epoch < unlocking_start {
+                    Ok(PositionState::LOCKED)
+                } else if current_epoch < self.unlocking_completion_epoch(unlocking_duration, unlocking_start)? {
+                    Ok(PositionState::UNLOCKING)
+                } else {
+                    Ok(PositionState::UNLOCKED)
+                }
+            }
+        }
+    }
+
+    /// A position still counts toward voting power while it's LOCKING, LOCKED, or UNLOCKING;
+    /// only once a position has fully UNLOCKED does it stop voting.
+    pub fn is_voting(&self, current_epoch: u64, unlocking_duration: u8) -> Result<bool> {
+        Ok(!matches!(
+            self.get_current_position(current_epoch, unlocking_duration)?,
+            PositionState::UNLOCKED
+        ))
+    }
+
+    /// The epoch at which an in-flight unlock finishes and the position's tokens become
+    /// withdrawable, or `None` if unlocking has never been requested for the position. The
+    /// epoch is returned whenever `unlocking_start` is set, even if that epoch has already passed.
+    pub fn completion_epoch(&self, unlocking_duration: u8) -> Result<Option<u64>> {
+        match self.unlocking_start {
+            None => Ok(None),
+            Some(unlocking_start) => Ok(Some(
+                self.unlocking_completion_epoch(unlocking_duration, unlocking_start)?,
+            )),
+        }
+    }
+
+    fn unlocking_completion_epoch(&self, unlocking_duration: u8, unlocking_start: u64) -> Result<u64> {
+        unlocking_start
+            .checked_add(unlocking_duration as u64)
+            .ok_or_else(|| error!(ErrorCode::GenericOverflow))
+    }
+}
+
+/// Summary of a slash applied to a stake account, broken down by the kind of position the
+/// slashed tokens were taken from.
+pub struct SlashResult {
+    pub slashed_from_locked: u64,
+    pub slashed_from_unlocking: u64,
+    pub remaining: u64,
+}
+
+#[account]
+pub struct PositionData {
+    pub owner: Pubkey,
+    pub positions: [Option<Position>; MAX_POSITIONS],
+}
+
+impl PositionData {
+    /// Finds first index available for a new position
+    pub fn get_unused_index(&self) -> Result<usize> {
+        self.positions
+            .iter()
+            .position(|p| p.is_none())
+            .ok_or_else(|| error!(ErrorCode::TooManyPositions))
+    }
+
+    /// Distributes `slash_amount` across this account's positions as of `slash_epoch`.
+    ///
+    /// LOCKED/LOCKING positions are consumed first (in position-index order). Anything left
+    /// over (`slash_remaining`) is then spread proportionally across UNLOCKING positions whose
+    /// unlock hasn't completed yet: with `to_slash = min(slash_remaining, affected_sum)`, each
+    /// position is cut by `floor(position.amount * to_slash / affected_sum)`, and the rounding
+    /// dust from that division goes to the affected positions, largest first, so the cuts always
+    /// sum to exactly `to_slash` and never touch positions outside the affected set.
+    pub fn slash(
+        &mut self,
+        slash_amount: u64,
+        slash_epoch: u64,
+        unlocking_duration: u8,
+    ) -> Result<SlashResult> {
+        let mut remaining = slash_amount;
+        let mut slashed_from_locked: u64 = 0;
+
+        for maybe_position in self.positions.iter_mut() {
+            if remaining == 0 {
+                break;
+            }
+            if let Some(position) = maybe_position {
+                if matches!(
+                    position.get_current_position(slash_epoch, unlocking_duration)?,
+                    PositionState::LOCKED | PositionState::LOCKING
+                ) {
+                    let cut = remaining.min(position.amount);
+                    position.amount = position
+                        .amount
+                        .checked_sub(cut)
+                        .ok_or_else(|| error!(ErrorCode::GenericOverflow))?;
+                    remaining = remaining
+                        .checked_sub(cut)
+                        .ok_or_else(|| error!(ErrorCode::GenericOverflow))?;
+                    slashed_from_locked = slashed_from_locked
+                        .checked_add(cut)
+                        .ok_or_else(|| error!(ErrorCode::GenericOverflow))?;
+                }
+            }
+        }
+
+        let mut slashed_from_unlocking: u64 = 0;
+        if remaining > 0 {
+            let mut affected: Vec<usize> = Vec::new();
+            let mut affected_sum: u64 = 0;
+            for (i, maybe_position) in self.positions.iter().enumerate() {
+                if let Some(position) = maybe_position {
+                    if matches!(
+                        position.get_current_position(slash_epoch, unlocking_duration)?,
+                        PositionState::UNLOCKING
+                    ) {
+                        affected.push(i);
+                        affected_sum = affected_sum
+                            .checked_add(position.amount)
+                            .ok_or_else(|| error!(ErrorCode::GenericOverflow))?;
+                    }
+                }
+            }
+
+            if !affected.is_empty() && affected_sum > 0 {
+                let to_slash = remaining.min(affected_sum);
+                let mut cuts: Vec<u64> = Vec::with_capacity(affected.len());
+                let mut cuts_sum: u64 = 0;
+                for &i in &affected {
+                    let amount = self.positions[i].unwrap().amount;
+                    let cut = ((amount as u128) * (to_slash as u128) / (affected_sum as u128)) as u64;
+                    cuts_sum = cuts_sum
+                        .checked_add(cut)
+                        .ok_or_else(|| error!(ErrorCode::GenericOverflow))?;
+                    cuts.push(cut);
+                }
+
+                // The floor division above can leave dust uncut; absorb it into the affected
+                // positions, largest first and never more than a position still holds, rather than
+                // letting it leak onto positions we never touched.
+                let mut dust = to_slash
+                    .checked_sub(cuts_sum)
+                    .ok_or_else(|| error!(ErrorCode::GenericOverflow))?;
+                let mut by_size: Vec<usize> = (0..affected.len()).collect();
+                by_size.sort_by_key(|&k| std::cmp::Reverse(self.positions[affected[k]].unwrap().amount));
+                for k in by_size {
+                    if dust == 0 {
+                        break;
+                    }
+                    let room = self.positions[affected[k]].unwrap().amount.saturating_sub(cuts[k]);
+                    let extra = dust.min(room);
+                    cuts[k] += extra;
+                    dust -= extra;
+                }
+
+                for (&i, &cut) in affected.iter().zip(cuts.iter()) {
+                    let position = self.positions[i].as_mut().unwrap();
+                    position.amount = position
+                        .amount
+                        .checked_sub(cut)
+                        .ok_or_else(|| error!(ErrorCode::GenericOverflow))?;
+                }
+
+                slashed_from_unlocking = to_slash;
+                remaining = remaining
+                    .checked_sub(to_slash)
+                    .ok_or_else(|| error!(ErrorCode::GenericOverflow))?;
+            }
+        }
+
+        Ok(SlashResult {
+            slashed_from_locked,
+            slashed_from_unlocking,
+            remaining,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UNLOCKING_DURATION: u8 = 10;
+    const SLASH_EPOCH: u64 = 100;
+
+    fn locked(amount: u64) -> Position {
+        Position {
+            amount,
+            activation_epoch: 0,
+            unlocking_start: None,
+        }
+    }
+
+    fn unlocking(amount: u64, unlocking_start: u64) -> Position {
+        Position {
+            amount,
+            activation_epoch: 0,
+            unlocking_start: Some(unlocking_start),
+        }
+    }
+
+    fn position_data(positions: Vec<Position>) -> PositionData {
+        let mut slots: [Option<Position>; MAX_POSITIONS] = [None; MAX_POSITIONS];
+        for (i, position) in positions.into_iter().enumerate() {
+            slots[i] = Some(position);
+        }
+        PositionData {
+            owner: Pubkey::default(),
+            positions: slots,
+        }
+    }
+
+    #[test]
+    fn slash_consumes_locked_before_unlocking() {
+        let mut data = position_data(vec![locked(50), unlocking(100, SLASH_EPOCH - 1)]);
+        let result = data.slash(30, SLASH_EPOCH, UNLOCKING_DURATION).unwrap();
+        assert_eq!(result.slashed_from_locked, 30);
+        assert_eq!(result.slashed_from_unlocking, 0);
+        assert_eq!(result.remaining, 0);
+        assert_eq!(data.positions[0].unwrap().amount, 20);
+        assert_eq!(data.positions[1].unwrap().amount, 100);
+    }
+
+    #[test]
+    fn slash_distributes_proportionally_across_unlocking_and_absorbs_dust() {
+        // Both positions are UNLOCKING as of SLASH_EPOCH (unlocking_start <= epoch < start + duration).
+        let mut data = position_data(vec![
+            unlocking(100, SLASH_EPOCH - 1),
+            unlocking(201, SLASH_EPOCH - 1),
+        ]);
+        let result = data.slash(100, SLASH_EPOCH, UNLOCKING_DURATION).unwrap();
+        assert_eq!(result.slashed_from_locked, 0);
+        assert_eq!(result.slashed_from_unlocking, 100);
+        assert_eq!(result.remaining, 0);
+
+        let cut_a = 100 - data.positions[0].unwrap().amount;
+        let cut_b = 201 - data.positions[1].unwrap().amount;
+        // sum(cuts) == min(slash_amount, sum(affected)) exactly, dust included.
+        assert_eq!(cut_a + cut_b, 100);
+    }
+
+    #[test]
+    fn slash_leaves_matured_unlocked_positions_untouched() {
+        let mut data = position_data(vec![unlocking(100, SLASH_EPOCH - UNLOCKING_DURATION as u64 - 1)]);
+        let result = data.slash(50, SLASH_EPOCH, UNLOCKING_DURATION).unwrap();
+        assert_eq!(result.slashed_from_unlocking, 0);
+        assert_eq!(result.remaining, 50);
+        assert_eq!(data.positions[0].unwrap().amount, 100);
+    }
+
+    #[test]
+    fn slash_reports_remaining_when_amount_exceeds_total_stake() {
+        let mut data = position_data(vec![locked(10), unlocking(10, SLASH_EPOCH - 1)]);
+        let result = data.slash(100, SLASH_EPOCH, UNLOCKING_DURATION).unwrap();
+        assert_eq!(result.slashed_from_locked, 10);
+        assert_eq!(result.slashed_from_unlocking, 10);
+        assert_eq!(result.remaining, 80);
+        assert_eq!(data.positions[0].unwrap().amount, 0);
+        assert_eq!(data.positions[1].unwrap().amount, 0);
+    }
+
+    #[test]
+    fn slash_zero_amount_is_a_no_op() {
+        let mut data = position_data(vec![locked(50)]);
+        let result = data.slash(0, SLASH_EPOCH, UNLOCKING_DURATION).unwrap();
+        assert_eq!(result.slashed_from_locked, 0);
+        assert_eq!(result.remaining, 0);
+        assert_eq!(data.positions[0].unwrap().amount, 50);
+    }
+}
@@ -0,0 +1,196 @@
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+
+/// Fixed-point scale for `VestingControlPoint::vested_fraction`: a fraction of
+/// `VESTED_FRACTION_SCALE` represents 100% vested.
+pub const VESTED_FRACTION_SCALE: u64 = 1_000_000;
+
+/// One point on a piecewise-linear vesting curve: at `unix_time`, `vested_fraction` out of
+/// `VESTED_FRACTION_SCALE` of the grant has vested.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct VestingControlPoint {
+    pub unix_time: i64,
+    pub vested_fraction: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub enum VestingSchedule {
+    FullyVested,
+    PeriodicVesting {
+        initial_balance: u64,
+        start_date: i64,
+        period_duration: u64,
+        num_periods: u64,
+    },
+    /// An arbitrary cliff-plus-ramp curve described as a sorted list of control points, for
+    /// grants whose vesting can't be expressed as a single cliff/period pair.
+    PiecewiseLinear {
+        initial_balance: u64,
+        control_points: Vec<VestingControlPoint>,
+    },
+}
+
+impl VestingSchedule {
+    pub fn get_unvested_balance(&self, current_time: i64) -> Result<u64> {
+        match self {
+            VestingSchedule::FullyVested => Ok(0),
+            VestingSchedule::PeriodicVesting {
+                initial_balance,
+                start_date,
+                period_duration,
+                num_periods,
+            } => {
+                if current_time < *start_date {
+                    Ok(*initial_balance)
+                } else {
+                    let periods_elapsed = ((current_time - start_date) as u64) / period_duration;
+                    if periods_elapsed >= *num_periods {
+                        Ok(0)
+                    } else {
+                        let vested = (*initial_balance)
+                            .checked_mul(periods_elapsed)
+                            .ok_or_else(|| error!(ErrorCode::GenericOverflow))?
+                            .checked_div(*num_periods)
+                            .ok_or_else(|| error!(ErrorCode::GenericOverflow))?;
+                        Ok(initial_balance - vested)
+                    }
+                }
+            }
+            VestingSchedule::PiecewiseLinear {
+                initial_balance,
+                control_points,
+            } => {
+                Self::validate_control_points(control_points)?;
+                let vested_fraction = Self::vested_fraction_at(control_points, current_time)?;
+                let vested = (*initial_balance as u128)
+                    .checked_mul(vested_fraction as u128)
+                    .ok_or_else(|| error!(ErrorCode::GenericOverflow))?
+                    / (VESTED_FRACTION_SCALE as u128);
+                let vested = u64::try_from(vested).map_err(|_| error!(ErrorCode::GenericOverflow))?;
+                initial_balance
+                    .checked_sub(vested)
+                    .ok_or_else(|| error!(ErrorCode::GenericOverflow))
+            }
+        }
+    }
+
+    /// Control points must be non-empty (so `vested_fraction_at` always has a first/last point
+    /// to clamp against), strictly increasing in time (so every segment has a well-defined
+    /// slope), non-decreasing in vested fraction (so the curve never un-vests tokens), and
+    /// bounded by `VESTED_FRACTION_SCALE` (so no point claims more than 100% vested).
+    fn validate_control_points(control_points: &[VestingControlPoint]) -> Result<()> {
+        if control_points.is_empty() {
+            return Err(error!(ErrorCode::VestingScheduleNotMonotonic));
+        }
+        if control_points.iter().any(|p| p.vested_fraction > VESTED_FRACTION_SCALE) {
+            return Err(error!(ErrorCode::VestingScheduleNotMonotonic));
+        }
+        for pair in control_points.windows(2) {
+            if pair[1].unix_time <= pair[0].unix_time || pair[1].vested_fraction < pair[0].vested_fraction {
+                return Err(error!(ErrorCode::VestingScheduleNotMonotonic));
+            }
+        }
+        Ok(())
+    }
+
+    /// Binary-searches the segment containing `t` and linearly interpolates within it, clamping
+    /// to 0% before the first control point and 100% after the last.
+    fn vested_fraction_at(control_points: &[VestingControlPoint], t: i64) -> Result<u64> {
+        let first = control_points.first().unwrap();
+        let last = control_points.last().unwrap();
+        if t < first.unix_time {
+            return Ok(0);
+        }
+        if t > last.unix_time {
+            return Ok(VESTED_FRACTION_SCALE);
+        }
+
+        let idx = match control_points.binary_search_by_key(&t, |p| p.unix_time) {
+            Ok(i) => return Ok(control_points[i].vested_fraction),
+            Err(i) => i,
+        };
+        let p0 = &control_points[idx - 1];
+        let p1 = &control_points[idx];
+
+        let frac_range = (p1.vested_fraction - p0.vested_fraction) as i128;
+        let time_range = (p1.unix_time - p0.unix_time) as i128;
+        let elapsed = (t - p0.unix_time) as i128;
+        let interpolated = frac_range
+            .checked_mul(elapsed)
+            .ok_or_else(|| error!(ErrorCode::GenericOverflow))?
+            .checked_div(time_range)
+            .ok_or_else(|| error!(ErrorCode::GenericOverflow))?;
+        u64::try_from(p0.vested_fraction as i128 + interpolated).map_err(|_| error!(ErrorCode::GenericOverflow))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve(initial_balance: u64, control_points: Vec<(i64, u64)>) -> VestingSchedule {
+        VestingSchedule::PiecewiseLinear {
+            initial_balance,
+            control_points: control_points
+                .into_iter()
+                .map(|(unix_time, vested_fraction)| VestingControlPoint {
+                    unix_time,
+                    vested_fraction,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn piecewise_linear_rejects_empty_control_points() {
+        let vs = curve(1_000, vec![]);
+        assert!(vs.get_unvested_balance(0).is_err());
+    }
+
+    #[test]
+    fn piecewise_linear_rejects_out_of_range_fraction() {
+        let vs = curve(1_000, vec![(0, 0), (100, VESTED_FRACTION_SCALE + 1)]);
+        assert!(vs.get_unvested_balance(50).is_err());
+    }
+
+    #[test]
+    fn piecewise_linear_rejects_non_monotonic_time() {
+        let vs = curve(1_000, vec![(100, 0), (0, VESTED_FRACTION_SCALE)]);
+        assert!(vs.get_unvested_balance(50).is_err());
+    }
+
+    #[test]
+    fn piecewise_linear_clamps_before_first_and_after_last() {
+        let vs = curve(1_000, vec![(100, 0), (200, VESTED_FRACTION_SCALE)]);
+        assert_eq!(vs.get_unvested_balance(0).unwrap(), 1_000);
+        assert_eq!(vs.get_unvested_balance(300).unwrap(), 0);
+    }
+
+    #[test]
+    fn piecewise_linear_exact_match_at_control_point() {
+        let vs = curve(1_000, vec![(0, 0), (100, VESTED_FRACTION_SCALE / 2), (200, VESTED_FRACTION_SCALE)]);
+        assert_eq!(vs.get_unvested_balance(100).unwrap(), 500);
+    }
+
+    #[test]
+    fn piecewise_linear_interpolates_midway() {
+        let vs = curve(1_000, vec![(0, 0), (200, VESTED_FRACTION_SCALE)]);
+        assert_eq!(vs.get_unvested_balance(100).unwrap(), 500);
+    }
+
+    #[test]
+    fn fully_vested_has_no_unvested_balance() {
+        assert_eq!(VestingSchedule::FullyVested.get_unvested_balance(0).unwrap(), 0);
+    }
+
+    #[test]
+    fn periodic_vesting_before_start_is_fully_unvested() {
+        let vs = VestingSchedule::PeriodicVesting {
+            initial_balance: 1_000,
+            start_date: 100,
+            period_duration: 10,
+            num_periods: 10,
+        };
+        assert_eq!(vs.get_unvested_balance(0).unwrap(), 1_000);
+    }
+}
@@ -0,0 +1,30 @@
+use crate::error::ErrorCode;
+use crate::state::positions::{PositionData, PositionState};
+use anchor_lang::prelude::*;
+
+/// Computes how much of `total_balance` a stake account is allowed to withdraw right now:
+/// everything except whatever is still locked/locking/unlocking or unvested.
+pub fn validate(
+    position_data: &PositionData,
+    total_balance: u64,
+    unvested_balance: u64,
+    current_epoch: u64,
+    unlocking_duration: u8,
+) -> Result<u64> {
+    let mut unwithdrawable: u64 = unvested_balance;
+
+    for maybe_position in position_data.positions.iter() {
+        if let Some(position) = maybe_position {
+            match position.get_current_position(current_epoch, unlocking_duration)? {
+                PositionState::LOCKING | PositionState::LOCKED | PositionState::UNLOCKING => {
+                    unwithdrawable = unwithdrawable
+                        .checked_add(position.amount)
+                        .ok_or_else(|| error!(ErrorCode::GenericOverflow))?;
+                }
+                PositionState::UNLOCKED => {}
+            }
+        }
+    }
+
+    Ok(total_balance.saturating_sub(unwithdrawable))
+}
@@ -20,6 +20,19 @@ pub struct LockedBalanceSummary {
     pub unlocking: u64
 }
 
+#[wasm_bindgen]
+pub struct SlashSummary {
+    pub slashed_from_locked: u64,
+    pub slashed_from_unlocking: u64,
+    pub remaining: u64,
+}
+
+#[wasm_bindgen]
+pub struct UnlockScheduleEntry {
+    pub epoch: u64,
+    pub amount: u64,
+}
+
 #[wasm_bindgen]
 impl WasmPositionData {
     #[wasm_bindgen(constructor)]
@@ -50,7 +63,7 @@ impl WasmPositionData {
     }
     fn is_position_voting_impl(&self, index: u16, current_epoch: u64, unlocking_duration: u8) -> anchor_lang::Result<bool> {
         match self.wrapped.positions[index as usize] {
-            Some(pos) => Ok(pos.is_voting()),
+            Some(pos) => Ok(pos.is_voting(current_epoch, unlocking_duration)?),
             None => Err(error!(ErrorCode::PositionNotInUse))
         }
     }
@@ -127,6 +140,57 @@ impl WasmPositionData {
     pub fn get_unused_index(&self) -> Result<usize, JsValue> {
         convert_error(self.wrapped.get_unused_index())
     }
+
+    /// Distributes a slash across this account's positions as of `slash_epoch`, mutating them
+    /// in place. See `PositionData::slash` for the consume-locked-then-pro-rate-unlocking
+    /// algorithm.
+    #[wasm_bindgen(js_name=slash)]
+    pub fn slash(&mut self, slash_amount: u64, slash_epoch: u64, unlocking_duration: u8) -> Result<SlashSummary, JsValue> {
+        convert_error(self.slash_impl(slash_amount, slash_epoch, unlocking_duration))
+    }
+    fn slash_impl(&mut self, slash_amount: u64, slash_epoch: u64, unlocking_duration: u8) -> anchor_lang::Result<SlashSummary> {
+        let result = self.wrapped.slash(slash_amount, slash_epoch, unlocking_duration)?;
+        Ok(SlashSummary {
+            slashed_from_locked: result.slashed_from_locked,
+            slashed_from_unlocking: result.slashed_from_unlocking,
+            remaining: result.remaining,
+        })
+    }
+
+    /// Walks every UNLOCKING position and buckets its amount by the epoch its unlock completes,
+    /// so front-ends can draw a withdrawal timeline instead of the single collapsed "unlocking"
+    /// number from `getLockedBalanceSummary`. Entries are sorted ascending by epoch.
+    #[wasm_bindgen(js_name=getUnlockSchedule)]
+    pub fn get_unlock_schedule(&self, current_epoch: u64, unlocking_duration: u8) -> Result<js_sys::Array, JsValue> {
+        convert_error(self.get_unlock_schedule_impl(current_epoch, unlocking_duration))
+    }
+    fn get_unlock_schedule_impl(&self, current_epoch: u64, unlocking_duration: u8) -> anchor_lang::Result<js_sys::Array> {
+        let mut buckets: Vec<(u64, u64)> = Vec::new();
+
+        for i in 0..crate::MAX_POSITIONS {
+            if let Some(position) = self.wrapped.positions[i] {
+                if let PositionState::UNLOCKING = position.get_current_position(current_epoch, unlocking_duration)? {
+                    let completion_epoch = position
+                        .completion_epoch(unlocking_duration)?
+                        .expect("UNLOCKING position always has a completion epoch");
+                    match buckets.iter_mut().find(|(epoch, _)| *epoch == completion_epoch) {
+                        Some((_, amount)) => {
+                            *amount = amount.checked_add(position.amount).ok_or(error!(ErrorCode::GenericOverflow))?;
+                        }
+                        None => buckets.push((completion_epoch, position.amount)),
+                    }
+                }
+            }
+        }
+
+        buckets.sort_by_key(|(epoch, _)| *epoch);
+
+        let entries = js_sys::Array::new();
+        for (epoch, amount) in buckets {
+            entries.push(&JsValue::from(UnlockScheduleEntry { epoch, amount }));
+        }
+        Ok(entries)
+    }
 }
 
 #[wasm_bindgen(js_name=getUnvestedBalance)]